@@ -1,11 +1,55 @@
-use std::{io::{self, Read}, thread, time::Duration, f32::consts::PI, sync::{Arc, Mutex}};
+use std::{io, thread, time::Duration};
 use std::sync::mpsc;
+use std::ops::{Add, Sub, Mul, Neg};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+
+// Fallback dimensions when the terminal size can't be queried.
+const DEFAULT_WIDTH: usize = 80;
+const DEFAULT_HEIGHT: usize = 24;
+// SCALE is tuned against this height; other sizes scale proportionally.
+const BASE_HEIGHT: f32 = 24.0;
+const BASE_SCALE: f32 = 20.0;
 
-const WIDTH: usize = 80;
-const HEIGHT: usize = 24;
 const CUBE_SIZE: f32 = 1.0;
 const DISTANCE: f32 = 3.0;
-const SCALE: f32 = 20.0;
+
+// Direction the light shines from, used for flat Lambertian shading of solid faces.
+// The negative z axis, expressed via Vec3::Z (field access is const-evaluable;
+// the Neg impl on Vec3 itself isn't, since operator traits aren't const fns).
+const LIGHT_DIR: Vec3 = Vec3 { x: -Vec3::Z.x, y: -Vec3::Z.y, z: -Vec3::Z.z };
+
+// Luminance-to-character ramp for solid shading, darkest to brightest.
+const SHADE_RAMP: &[u8] = b".,-~:;=!*#$@";
+
+// Camera sits on the negative z axis, looking toward the origin.
+const CAMERA: Vec3 = Vec3 { x: Vec3::Z.x * -DISTANCE, y: Vec3::Z.y * -DISTANCE, z: Vec3::Z.z * -DISTANCE };
+
+// Glyph used for vertices, distinct from the '#' used for edges.
+const NODE_MARKER: char = 'o';
+
+// Default ANSI 256-color palette indices for meshes that don't specify their own.
+const DEFAULT_LINE_COLOR: u8 = 6; // cyan
+const DEFAULT_NODE_COLOR: u8 = 3; // yellow
+
+// Screen dimensions and projection scale, resized to match the real terminal.
+struct Viewport {
+    width: usize,
+    height: usize,
+    scale: f32,
+}
+
+impl Viewport {
+    fn from_terminal() -> Viewport {
+        let (cols, rows) = terminal::size()
+            .map(|(cols, rows)| (cols as usize, rows as usize))
+            .unwrap_or((DEFAULT_WIDTH, DEFAULT_HEIGHT));
+        let width = cols.max(1);
+        let height = rows.max(1);
+        Viewport { width, height, scale: BASE_SCALE * (height as f32 / BASE_HEIGHT) }
+    }
+}
 
 #[derive(Clone, Copy, Debug)]
 struct Vec3 {
@@ -14,12 +58,70 @@ struct Vec3 {
     z: f32,
 }
 
+impl Vec3 {
+    const ZERO: Vec3 = Vec3 { x: 0.0, y: 0.0, z: 0.0 };
+    const X: Vec3 = Vec3 { x: 1.0, y: 0.0, z: 0.0 };
+    // Reserved alongside X/Z for axis-aligned math; no call site needs it yet.
+    #[allow(dead_code)]
+    const Y: Vec3 = Vec3 { x: 0.0, y: 1.0, z: 0.0 };
+    const Z: Vec3 = Vec3 { x: 0.0, y: 0.0, z: 1.0 };
+
+    fn dot(self, other: Vec3) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    fn cross(self, other: Vec3) -> Vec3 {
+        Vec3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    fn normalize(self) -> Vec3 {
+        let len = self.length();
+        if len == 0.0 { self } else { self * (1.0 / len) }
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+    fn add(self, other: Vec3) -> Vec3 {
+        Vec3 { x: self.x + other.x, y: self.y + other.y, z: self.z + other.z }
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+    fn sub(self, other: Vec3) -> Vec3 {
+        Vec3 { x: self.x - other.x, y: self.y - other.y, z: self.z - other.z }
+    }
+}
+
+impl Mul<f32> for Vec3 {
+    type Output = Vec3;
+    fn mul(self, scalar: f32) -> Vec3 {
+        Vec3 { x: self.x * scalar, y: self.y * scalar, z: self.z * scalar }
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+    fn neg(self) -> Vec3 {
+        Vec3 { x: -self.x, y: -self.y, z: -self.z }
+    }
+}
+
 // Rotation around X, Y, Z axes
 fn rotate(v: Vec3, ax: f32, ay: f32, az: f32) -> Vec3 {
     // Rotate around X
     let sinx = ax.sin();
     let cosx = ax.cos();
-    let mut v = Vec3 {
+    let v = Vec3 {
         x: v.x,
         y: v.y * cosx - v.z * sinx,
         z: v.y * sinx + v.z * cosx,
@@ -27,7 +129,7 @@ fn rotate(v: Vec3, ax: f32, ay: f32, az: f32) -> Vec3 {
     // Rotate around Y
     let siny = ay.sin();
     let cosy = ay.cos();
-    v = Vec3 {
+    let v = Vec3 {
         x: v.x * cosy + v.z * siny,
         y: v.y,
         z: -v.x * siny + v.z * cosy,
@@ -42,19 +144,30 @@ fn rotate(v: Vec3, ax: f32, ay: f32, az: f32) -> Vec3 {
     }
 }
 
-// Simple perspective projection
-fn project(v: Vec3) -> (usize, usize) {
-    let factor = SCALE / (v.z + DISTANCE);
-    let x = (v.x * factor + (WIDTH as f32) / 2.0) as isize;
-    let y = (v.y * factor + (HEIGHT as f32) / 2.0) as isize;
+// Simple perspective projection, returning the screen position and the
+// view-space depth (distance from the camera) for z-buffering.
+fn project(v: Vec3, viewport: &Viewport) -> (usize, usize, f32) {
+    let depth = v.z + DISTANCE;
+    let center = Vec3 { x: (viewport.width as f32) / 2.0, y: (viewport.height as f32) / 2.0, z: 0.0 };
+    let screen = v * (viewport.scale / depth) + center;
+    let x = screen.x as isize;
+    let y = screen.y as isize;
     (
-        x.clamp(0, (WIDTH - 1) as isize) as usize,
-        y.clamp(0, (HEIGHT - 1) as isize) as usize,
+        x.clamp(0, viewport.width as isize - 1) as usize,
+        y.clamp(0, viewport.height as isize - 1) as usize,
+        depth,
     )
 }
 
 // Bresenham's line algorithm
-fn draw_line((x0, y0): (usize, usize), (x1, y1): (usize, usize), screen: &mut [Vec<char>]) {
+fn draw_line(
+    (x0, y0): (usize, usize),
+    (x1, y1): (usize, usize),
+    color: u8,
+    viewport: &Viewport,
+    screen: &mut [Vec<char>],
+    colors: &mut [Vec<Option<u8>>],
+) {
     let (mut x0, mut y0, x1, y1) = (x0 as isize, y0 as isize, x1 as isize, y1 as isize);
     let dx = (x1 - x0).abs();
     let dy = -(y1 - y0).abs();
@@ -62,8 +175,9 @@ fn draw_line((x0, y0): (usize, usize), (x1, y1): (usize, usize), screen: &mut [V
     let sy = if y0 < y1 { 1 } else { -1 };
     let mut err = dx + dy;
     loop {
-        if x0 >= 0 && x0 < WIDTH as isize && y0 >= 0 && y0 < HEIGHT as isize {
+        if x0 >= 0 && x0 < viewport.width as isize && y0 >= 0 && y0 < viewport.height as isize {
             screen[y0 as usize][x0 as usize] = '#';
+            colors[y0 as usize][x0 as usize] = Some(color);
         }
         if x0 == x1 && y0 == y1 { break; }
         let e2 = 2 * err;
@@ -72,38 +186,86 @@ fn draw_line((x0, y0): (usize, usize), (x1, y1): (usize, usize), screen: &mut [V
     }
 }
 
-// Non-blocking input reader for arrow keys
-fn spawn_input_thread() -> mpsc::Receiver<(f32, f32)> {
-    let (tx, rx) = mpsc::channel();
-    thread::spawn(move || {
-        let stdin = io::stdin();
-        let mut stdin = stdin.lock();
-        let mut buf = [0u8; 3];
-        loop {
-            if let Ok(n) = stdin.read(&mut buf) {
-                if n == 3 && buf[0] == 27 && buf[1] == 91 {
-                    // Arrow keys
-                    match buf[2] {
-                        65 => { tx.send((0.1, 0.0)).ok(); } // Up
-                        66 => { tx.send((-0.1, 0.0)).ok(); } // Down
-                        67 => { tx.send((0.0, 0.1)).ok(); } // Right
-                        68 => { tx.send((0.0, -0.1)).ok(); } // Left
-                        _ => {}
-                    }
-                }
+// Marks a single screen cell with a distinct vertex glyph and color.
+fn draw_node(
+    (x, y): (usize, usize),
+    color: u8,
+    screen: &mut [Vec<char>],
+    colors: &mut [Vec<Option<u8>>],
+) {
+    screen[y][x] = NODE_MARKER;
+    colors[y][x] = Some(color);
+}
+
+// Fills one screen-space triangle, interpolating depth per pixel and
+// writing `ch` only where it's nearer than whatever is already in `depth`.
+fn rasterize_triangle(
+    p0: (f32, f32, f32),
+    p1: (f32, f32, f32),
+    p2: (f32, f32, f32),
+    ch: char,
+    viewport: &Viewport,
+    screen: &mut [Vec<char>],
+    depth: &mut [Vec<f32>],
+) {
+    let area = (p1.0 - p0.0) * (p2.1 - p0.1) - (p2.0 - p0.0) * (p1.1 - p0.1);
+    if area == 0.0 {
+        return;
+    }
+
+    let min_x = p0.0.min(p1.0).min(p2.0).floor().max(0.0) as usize;
+    let max_x = (p0.0.max(p1.0).max(p2.0).ceil() as isize).clamp(0, viewport.width as isize - 1) as usize;
+    let min_y = p0.1.min(p1.1).min(p2.1).floor().max(0.0) as usize;
+    let max_y = (p0.1.max(p1.1).max(p2.1).ceil() as isize).clamp(0, viewport.height as isize - 1) as usize;
+
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+            let w0 = ((p1.0 - px) * (p2.1 - py) - (p2.0 - px) * (p1.1 - py)) / area;
+            let w1 = ((p2.0 - px) * (p0.1 - py) - (p0.0 - px) * (p2.1 - py)) / area;
+            let w2 = 1.0 - w0 - w1;
+            if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                continue;
+            }
+            let z = w0 * p0.2 + w1 * p1.2 + w2 * p2.2;
+            if z < depth[y][x] {
+                depth[y][x] = z;
+                screen[y][x] = ch;
             }
         }
-    });
-    rx
+    }
 }
 
-fn main() {
-    // Set terminal to raw mode (Unix only)
-    #[cfg(unix)]
-    let _ = std::process::Command::new("stty").arg("raw").arg("-echo").status();
+// A mesh of points connected by edges (for wireframe rendering) and grouped
+// into faces (for solid shading and culling). Faces may be any polygon, not
+// just quads, so they triangulate as a fan when rasterized.
+struct Mesh {
+    points: Vec<Vec3>,
+    edges: Vec<(usize, usize)>,
+    faces: Vec<Vec<usize>>,
+    line_color: u8,
+    node_color: u8,
+}
 
-    // Vertices of a cube
-    let cube = [
+// Derives the unique boundary edges of a set of faces, deduplicating shared
+// edges between adjacent faces.
+fn edges_from_faces(faces: &[Vec<usize>]) -> Vec<(usize, usize)> {
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    for face in faces {
+        for i in 0..face.len() {
+            let a = face[i];
+            let b = face[(i + 1) % face.len()];
+            let edge = if a < b { (a, b) } else { (b, a) };
+            if !edges.contains(&edge) {
+                edges.push(edge);
+            }
+        }
+    }
+    edges
+}
+
+fn cube_mesh() -> Mesh {
+    let points = vec![
         Vec3 { x: -CUBE_SIZE, y: -CUBE_SIZE, z: -CUBE_SIZE },
         Vec3 { x:  CUBE_SIZE, y: -CUBE_SIZE, z: -CUBE_SIZE },
         Vec3 { x:  CUBE_SIZE, y:  CUBE_SIZE, z: -CUBE_SIZE },
@@ -113,61 +275,350 @@ fn main() {
         Vec3 { x:  CUBE_SIZE, y:  CUBE_SIZE, z:  CUBE_SIZE },
         Vec3 { x: -CUBE_SIZE, y:  CUBE_SIZE, z:  CUBE_SIZE },
     ];
-    // Edges between vertices
-    let edges = [
-        (0,1),(1,2),(2,3),(3,0), // back face
-        (4,5),(5,6),(6,7),(7,4), // front face
-        (0,4),(1,5),(2,6),(3,7), // connections
+    // Faces as outward-wound quads, shared by wireframe and solid rendering
+    let faces = vec![
+        vec![0, 3, 2, 1], // back  (z = -CUBE_SIZE)
+        vec![4, 5, 6, 7], // front (z = +CUBE_SIZE)
+        vec![0, 4, 7, 3], // left  (x = -CUBE_SIZE)
+        vec![1, 2, 6, 5], // right (x = +CUBE_SIZE)
+        vec![0, 1, 5, 4], // bottom (y = -CUBE_SIZE)
+        vec![3, 7, 6, 2], // top    (y = +CUBE_SIZE)
     ];
+    let edges = edges_from_faces(&faces);
+    Mesh { points, edges, faces, line_color: DEFAULT_LINE_COLOR, node_color: DEFAULT_NODE_COLOR }
+}
+
+// Parses a minimal Wavefront OBJ subset: `v x y z` vertex lines and
+// `f a b c ...` face lines (1-indexed, `vertex/texture/normal` trailers
+// ignored). Edges are derived from each face's boundary.
+fn load_obj(path: &str) -> Mesh {
+    let contents = std::fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("failed to read mesh file {path}: {e}"));
+
+    let mut points = Vec::new();
+    let mut faces = Vec::new();
+    for line in contents.lines() {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => {
+                let coords: Vec<f32> = tokens.filter_map(|t| t.parse().ok()).collect();
+                match coords[..] {
+                    [x, y, z] => points.push(Vec3 { x, y, z }),
+                    _ => panic!(
+                        "{path}: expected 3 coordinates on `v` line, found {} (vertex numbering would desync)",
+                        coords.len()
+                    ),
+                }
+            }
+            Some("f") => {
+                let face: Vec<usize> = tokens
+                    .filter_map(|t| t.split('/').next())
+                    .map(|t| {
+                        let index: usize = t
+                            .parse()
+                            .unwrap_or_else(|_| panic!("{path}: invalid face index {t:?}"));
+                        index
+                            .checked_sub(1)
+                            .unwrap_or_else(|| panic!("{path}: face index 0 is invalid (OBJ indices are 1-based)"))
+                    })
+                    .collect();
+                if face.len() >= 3 {
+                    faces.push(face);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let edges = edges_from_faces(&faces);
+    Mesh { points, edges, faces, line_color: DEFAULT_LINE_COLOR, node_color: DEFAULT_NODE_COLOR }
+}
+
+// Outward normal of a face, from the cross product of two of its edges.
+// The face index lists are wound so this already points away from the mesh.
+fn face_normal(rotated: &[Vec3], face: &[usize]) -> Vec3 {
+    let v0 = rotated[face[0]];
+    let v1 = rotated[face[1]];
+    let v2 = rotated[face[2]];
+    (v1 - v0).cross(v2 - v0)
+}
 
-    // Shared rotation angles
-    let angle_x = Arc::new(Mutex::new(0.0f32));
-    let angle_y = Arc::new(Mutex::new(0.0f32));
-    let rx = angle_x.clone();
-    let ry = angle_y.clone();
+fn face_centroid(rotated: &[Vec3], face: &[usize]) -> Vec3 {
+    let sum = face.iter().fold(Vec3::ZERO, |acc, &i| acc + rotated[i]);
+    sum * (1.0 / face.len() as f32)
+}
+
+// A face is back-facing if its outward normal points away from the camera.
+fn is_backface(rotated: &[Vec3], face: &[usize]) -> bool {
+    let normal = face_normal(rotated, face);
+    let to_camera = CAMERA - face_centroid(rotated, face);
+    normal.dot(to_camera) < 0.0
+}
+
+// Shades and fills every visible face of a mesh, fan-triangulating each
+// polygon and resolving occlusion through the shared depth buffer.
+fn draw_solid(
+    rotated: &[Vec3],
+    faces: &[Vec<usize>],
+    cull_backfaces: bool,
+    viewport: &Viewport,
+    screen: &mut [Vec<char>],
+    depth: &mut [Vec<f32>],
+) {
+    for face in faces {
+        if cull_backfaces && is_backface(rotated, face) {
+            continue;
+        }
+        let normal = face_normal(rotated, face).normalize();
+        let luminance = normal.dot(LIGHT_DIR).clamp(0.0, 1.0);
+        let index = (luminance * (SHADE_RAMP.len() - 1) as f32).round() as usize;
+        let ch = SHADE_RAMP[index] as char;
+
+        let pts: Vec<(f32, f32, f32)> = face
+            .iter()
+            .map(|&i| {
+                let (x, y, z) = project(rotated[i], viewport);
+                (x as f32, y as f32, z)
+            })
+            .collect();
+        for i in 1..pts.len() - 1 {
+            rasterize_triangle(pts[0], pts[i], pts[i + 1], ch, viewport, screen, depth);
+        }
+    }
+}
+
+// Draws each face's outline, skipping back-facing ones when culling is on;
+// otherwise draws the mesh's deduplicated edge list directly. Vertices are
+// drawn last so their marker and color sit on top of the connecting lines.
+fn draw_wireframe(
+    rotated: &[Vec3],
+    mesh: &Mesh,
+    cull_backfaces: bool,
+    viewport: &Viewport,
+    screen: &mut [Vec<char>],
+    colors: &mut [Vec<Option<u8>>],
+) {
+    if !cull_backfaces {
+        for &(a, b) in &mesh.edges {
+            let (x0, y0, _) = project(rotated[a], viewport);
+            let (x1, y1, _) = project(rotated[b], viewport);
+            draw_line((x0, y0), (x1, y1), mesh.line_color, viewport, screen, colors);
+        }
+    } else {
+        for face in &mesh.faces {
+            if is_backface(rotated, face) {
+                continue;
+            }
+            let corners: Vec<(usize, usize)> = face
+                .iter()
+                .map(|&i| {
+                    let (x, y, _) = project(rotated[i], viewport);
+                    (x, y)
+                })
+                .collect();
+            for k in 0..corners.len() {
+                draw_line(corners[k], corners[(k + 1) % corners.len()], mesh.line_color, viewport, screen, colors);
+            }
+        }
+    }
+
+    for &v in rotated {
+        let (x, y, _) = project(v, viewport);
+        draw_node((x, y), mesh.node_color, screen, colors);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum RenderMode {
+    Wireframe,
+    Solid,
+}
+
+// One placement of a mesh within the scene: which mesh it references, and
+// its own scale, rotation and position. Several instances can share a mesh.
+struct Instance {
+    mesh_index: usize,
+    translation: Vec3,
+    scale: f32,
+    angles: (f32, f32, f32),
+}
+
+impl Instance {
+    fn world_points(&self, mesh: &Mesh) -> Vec<Vec3> {
+        let (ax, ay, az) = self.angles;
+        mesh.points
+            .iter()
+            .map(|&p| rotate(p * self.scale, ax, ay, az) + self.translation)
+            .collect()
+    }
+}
+
+// Horizontal gap between instances when a scene is laid out automatically.
+const INSTANCE_SPACING: f32 = 3.0;
+
+// Non-blocking input reader for arrow keys, mode toggling, and instance selection
+enum InputEvent {
+    Rotate(f32, f32),
+    ToggleMode,
+    ToggleCull,
+    CycleInstance,
+    Quit,
+}
+
+// Reads keyboard events through crossterm, which works the same way on
+// Windows and Unix, instead of hand-parsing raw escape sequences from stdin.
+fn spawn_input_thread() -> mpsc::Receiver<InputEvent> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || while let Ok(event) = event::read() {
+        let Event::Key(key) = event else { continue };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let input_event = match key.code {
+            KeyCode::Up => Some(InputEvent::Rotate(0.1, 0.0)),
+            KeyCode::Down => Some(InputEvent::Rotate(-0.1, 0.0)),
+            KeyCode::Right => Some(InputEvent::Rotate(0.0, 0.1)),
+            KeyCode::Left => Some(InputEvent::Rotate(0.0, -0.1)),
+            KeyCode::Char('m') => Some(InputEvent::ToggleMode),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Some(InputEvent::Quit),
+            KeyCode::Char('c') => Some(InputEvent::ToggleCull),
+            KeyCode::Tab => Some(InputEvent::CycleInstance),
+            KeyCode::Esc => Some(InputEvent::Quit),
+            _ => None,
+        };
+        if let Some(input_event) = input_event {
+            if tx.send(input_event).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+// Puts the terminal into raw mode and restores it on drop, so the terminal
+// is left in a sane state however the program exits (including Ctrl+C,
+// which raw mode delivers as a key event rather than a signal).
+struct RawModeGuard;
+
+impl RawModeGuard {
+    fn new() -> io::Result<RawModeGuard> {
+        terminal::enable_raw_mode()?;
+        Ok(RawModeGuard)
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+// Builds one instance per mesh, spread out evenly along the x axis so a
+// multi-mesh scene doesn't start with everything piled on the origin.
+fn layout_instances(meshes: &[Mesh]) -> Vec<Instance> {
+    let n = meshes.len();
+    (0..n)
+        .map(|i| Instance {
+            mesh_index: i,
+            translation: Vec3::X * ((i as f32 - (n - 1) as f32 / 2.0) * INSTANCE_SPACING),
+            scale: 1.0,
+            angles: (0.0, 0.0, 0.0),
+        })
+        .collect()
+}
+
+fn main() -> io::Result<()> {
+    let _raw_mode = RawModeGuard::new()?;
+
+    // Load the meshes named on the command line, falling back to a single built-in cube
+    let mesh_paths: Vec<String> = std::env::args().skip(1).collect();
+    let meshes: Vec<Mesh> = if mesh_paths.is_empty() {
+        vec![cube_mesh()]
+    } else {
+        mesh_paths.iter().map(|path| load_obj(path)).collect()
+    };
+    let mut instances = layout_instances(&meshes);
+    let mut active = 0usize;
 
     // Input thread
     let input_rx = spawn_input_thread();
+    let mut mode = RenderMode::Solid;
+    let mut cull_backfaces = true;
 
     // Animation loop
     loop {
         // Handle input
-        while let Ok((dx, dy)) = input_rx.try_recv() {
-            *rx.lock().unwrap() += dx;
-            *ry.lock().unwrap() += dy;
+        while let Ok(event) = input_rx.try_recv() {
+            match event {
+                InputEvent::Rotate(dx, dy) => {
+                    let angles = &mut instances[active].angles;
+                    angles.0 += dx;
+                    angles.1 += dy;
+                    angles.2 += dx * 0.5 + dy * 0.5; // for a bit of extra spin
+                }
+                InputEvent::ToggleMode => {
+                    mode = match mode {
+                        RenderMode::Wireframe => RenderMode::Solid,
+                        RenderMode::Solid => RenderMode::Wireframe,
+                    };
+                }
+                InputEvent::ToggleCull => {
+                    cull_backfaces = !cull_backfaces;
+                }
+                InputEvent::CycleInstance => {
+                    active = (active + 1) % instances.len();
+                }
+                InputEvent::Quit => return Ok(()),
+            }
         }
 
         // Clear screen
         print!("\x1B[2J\x1B[1;1H");
 
-        let ax = *rx.lock().unwrap();
-        let ay = *ry.lock().unwrap();
-        let az = ax * 0.5 + ay * 0.5; // for a bit of extra spin
-
-        // Rotate and project
-        let projected: Vec<(usize, usize)> = cube.iter()
-            .map(|&v| {
-                let rotated = rotate(v, ax, ay, az);
-                project(rotated)
-            })
-            .collect();
+        // Re-read the terminal size every frame so resizes take effect live
+        let viewport = Viewport::from_terminal();
+        let mut screen = vec![vec![' '; viewport.width]; viewport.height];
+        let mut depth = vec![vec![f32::INFINITY; viewport.width]; viewport.height];
+        let mut colors: Vec<Vec<Option<u8>>> = vec![vec![None; viewport.width]; viewport.height];
 
-        // Draw edges
-        let mut screen = vec![vec![' '; WIDTH]; HEIGHT];
-        for &(a, b) in &edges {
-            draw_line(projected[a], projected[b], &mut screen);
+        for instance in &instances {
+            let mesh = &meshes[instance.mesh_index];
+            let rotated = instance.world_points(mesh);
+            match mode {
+                RenderMode::Wireframe => {
+                    draw_wireframe(&rotated, mesh, cull_backfaces, &viewport, &mut screen, &mut colors);
+                }
+                RenderMode::Solid => {
+                    draw_solid(&rotated, &mesh.faces, cull_backfaces, &viewport, &mut screen, &mut depth);
+                }
+            }
         }
 
-        // Print screen
-        for row in screen {
-            println!("{}", row.iter().collect::<String>());
+        // Print screen, switching ANSI color only where it changes and
+        // resetting once the whole frame has been written
+        let mut current_color: Option<u8> = None;
+        let mut frame = String::new();
+        for y in 0..viewport.height {
+            for x in 0..viewport.width {
+                if colors[y][x] != current_color {
+                    match colors[y][x] {
+                        Some(c) => frame.push_str(&format!("\x1B[38;5;{}m", c)),
+                        None => frame.push_str("\x1B[39m"),
+                    }
+                    current_color = colors[y][x];
+                }
+                frame.push(screen[y][x]);
+            }
+            frame.push('\n');
         }
-        println!("Use arrow keys to rotate. Ctrl+C to exit.");
+        frame.push_str("\x1B[0m");
+        print!("{frame}");
+        println!(
+            "Arrow keys steer instance {}/{}, Tab cycles it, 'm' toggles wireframe/solid, 'c' toggles culling, Ctrl+C/Esc to exit.",
+            active + 1,
+            instances.len()
+        );
 
         thread::sleep(Duration::from_millis(30));
     }
-
-    // Restore terminal mode (Unix only)
-    #[cfg(unix)]
-    let _ = std::process::Command::new("stty").arg("-raw").arg("echo").status();
-} 
\ No newline at end of file
+}